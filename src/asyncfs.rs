@@ -5,26 +5,150 @@ use aws_config::SdkConfig;
 use aws_sdk_s3::Client;
 use aws_sdk_s3::error::SdkError;
 use aws_sdk_s3::operation::get_object::{GetObjectError, GetObjectOutput};
+use aws_sdk_s3::presigning::PresigningConfig;
 use aws_sdk_s3::primitives::ByteStream;
-use aws_sdk_s3::types::ChecksumMode;
+use aws_sdk_s3::types::{
+    ChecksumMode,
+    CompletedMultipartUpload,
+    CompletedPart,
+};
 use aws_smithy_runtime_api::client::orchestrator::HttpResponse;
 use base64::Engine;
-use base64::engine::general_purpose::{STANDARD as base64_engine};
+use base64::engine::general_purpose::{
+    STANDARD as base64_engine,
+    URL_SAFE_NO_PAD as url_safe_base64_engine,
+};
 use core::future::Future;
 use core::pin::Pin;
 use core::task::Poll;
 use pin_project_lite::pin_project;
-use tokio::io::{AsyncRead, ReadBuf};
+use tempfile::NamedTempFile;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
 use tokio_util::io::StreamReader;
 
-use flechasdb::asyncdb::io::{FileSystem, HashedFileIn};
+use flechasdb::asyncdb::io::{FileSystem, HashedFileIn, HashedFileOut};
 use flechasdb::error::Error;
 
+/// Compression codec applied to stored objects.
+///
+/// The `ring` digest and the S3 `checksum_sha256` are computed over the
+/// *compressed* stored bytes, so `verify()` checks storage integrity; callers
+/// always see the decompressed plaintext.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Codec {
+    /// No compression.
+    #[default]
+    None,
+    /// gzip (`Content-Encoding: gzip`).
+    Gzip,
+    /// Zstandard (`Content-Encoding: zstd`).
+    Zstd,
+}
+
+impl Codec {
+    /// `Content-Encoding` value for the codec, if any.
+    fn content_encoding(self) -> Option<&'static str> {
+        match self {
+            Codec::None => None,
+            Codec::Gzip => Some("gzip"),
+            Codec::Zstd => Some("zstd"),
+        }
+    }
+
+    /// Resolves the codec from an object's `Content-Encoding`.
+    fn from_content_encoding(encoding: Option<&str>) -> Self {
+        match encoding {
+            Some("gzip") => Codec::Gzip,
+            Some("zstd") => Codec::Zstd,
+            _ => Codec::None,
+        }
+    }
+
+    /// Streams `src` through the codec into `dst`.
+    ///
+    /// Neither side is held in memory in full, so a multi-gigabyte partition
+    /// is compressed with a bounded footprint.
+    fn encode_stream(
+        self,
+        mut src: impl std::io::Read,
+        dst: impl std::io::Write,
+    ) -> std::io::Result<()> {
+        match self {
+            Codec::None => {
+                let mut dst = dst;
+                std::io::copy(&mut src, &mut dst)?;
+                Ok(())
+            },
+            Codec::Gzip => {
+                use flate2::Compression;
+                use flate2::write::GzEncoder;
+                let mut encoder = GzEncoder::new(dst, Compression::default());
+                std::io::copy(&mut src, &mut encoder)?;
+                encoder.finish()?;
+                Ok(())
+            },
+            Codec::Zstd => {
+                let mut encoder = zstd::stream::Encoder::new(dst, 0)?;
+                std::io::copy(&mut src, &mut encoder)?;
+                encoder.finish()?;
+                Ok(())
+            },
+        }
+    }
+
+    /// Decompresses the stored representation back to plaintext.
+    fn decode(self, stored: &[u8]) -> std::io::Result<Vec<u8>> {
+        match self {
+            Codec::None => Ok(stored.to_vec()),
+            Codec::Gzip => {
+                use std::io::Read as _;
+                use flate2::read::GzDecoder;
+                let mut out = Vec::new();
+                GzDecoder::new(stored).read_to_end(&mut out)?;
+                Ok(out)
+            },
+            Codec::Zstd => zstd::stream::decode_all(stored),
+        }
+    }
+}
+
+/// Object body above which a multipart upload is used instead of a single
+/// `put_object`.
+const MULTIPART_THRESHOLD: usize = 8 * 1024 * 1024;
+
+/// Size of every `UploadPart` but the last one.
+const MULTIPART_PART_SIZE: usize = 8 * 1024 * 1024;
+
+/// User metadata key holding the whole-object SHA-256 digest (Base64).
+///
+/// S3 reports a composite `checksum_sha256` for multipart objects, so the
+/// plain whole-object digest is carried as `x-amz-meta-sha256` instead.
+const SHA256_METADATA_KEY: &str = "sha256";
+
+/// Lightweight metadata about an object, as returned by `HeadObject`.
+#[derive(Clone, Debug)]
+pub struct ObjectMeta {
+    /// Size of the *stored* object body in bytes.
+    ///
+    /// When the file system that wrote the object had a codec configured
+    /// (`with_codec`), this is the compressed size, not the plaintext size a
+    /// caller would have tracked while writing.
+    pub content_length: u64,
+    /// Whole-object SHA-256 digest (Base64), if available.
+    ///
+    /// Prefers the `x-amz-meta-sha256` user metadata over the object's
+    /// `checksum_sha256`, which is composite for multipart objects.
+    pub sha256: Option<String>,
+    /// Last modification time reported by S3.
+    pub last_modified: Option<aws_smithy_types::DateTime>,
+}
+
 /// `FileSystem` on Amazon S3.
 pub struct S3FileSystem {
     s3: Client,
     bucket_name: String,
     base_path: String,
+    codec: Codec,
 }
 
 impl S3FileSystem {
@@ -39,26 +163,521 @@ impl S3FileSystem {
             s3,
             bucket_name: bucket_name.into(),
             base_path: base_path.into(),
+            codec: Codec::None,
+        }
+    }
+
+    /// Applies a compression codec to objects written through this file
+    /// system.
+    ///
+    /// Reads transparently decompress based on the object's
+    /// `Content-Encoding`, so this only affects newly written objects.
+    pub fn with_codec(mut self, codec: Codec) -> Self {
+        self.codec = codec;
+        self
+    }
+
+    /// Probes an object's existence and metadata without downloading it.
+    ///
+    /// Issues `HeadObject` with checksums enabled and returns `Ok(None)` when
+    /// the object does not exist, or `Ok(Some(meta))` with its content length,
+    /// SHA-256 checksum, and last-modified time otherwise.
+    pub async fn head(
+        &self,
+        path: impl AsRef<str>,
+    ) -> Result<Option<ObjectMeta>, Error> {
+        let key = format!("{}/{}", self.base_path, path.as_ref());
+        self.head_key(key).await
+    }
+
+    /// Probes an object by its fully resolved key.
+    async fn head_key(&self, key: String) -> Result<Option<ObjectMeta>, Error> {
+        let res = self.s3.head_object()
+            .bucket(&self.bucket_name)
+            .key(key)
+            .checksum_mode(ChecksumMode::Enabled)
+            .send()
+            .await;
+        match res {
+            Ok(res) => {
+                let sha256 = res.metadata()
+                    .and_then(|m| m.get(SHA256_METADATA_KEY))
+                    .cloned()
+                    .or_else(|| res.checksum_sha256().map(|c| c.to_string()));
+                Ok(Some(ObjectMeta {
+                    content_length: res.content_length().unwrap_or(0) as u64,
+                    sha256,
+                    last_modified: res.last_modified().cloned(),
+                }))
+            },
+            Err(e) => {
+                if e.as_service_error().map(|e| e.is_not_found()) == Some(true) {
+                    Ok(None)
+                } else {
+                    Err(Error::InvalidContext(
+                        format!("failed to head the object on S3: {}", e),
+                    ))
+                }
+            },
         }
     }
+
+    /// Mints a presigned URL to download the object at a given logical path.
+    ///
+    /// The URL is valid for `expires_in` and lets a client `GET` the object
+    /// `base_path/path` without AWS credentials.
+    pub async fn presign_get(
+        &self,
+        path: impl AsRef<str>,
+        expires_in: core::time::Duration,
+    ) -> Result<String, Error> {
+        let key = format!("{}/{}", self.base_path, path.as_ref());
+        let config = PresigningConfig::expires_in(expires_in)
+            .map_err(|e| Error::InvalidContext(
+                format!("invalid presigning config: {}", e),
+            ))?;
+        let req = self.s3.get_object()
+            .bucket(&self.bucket_name)
+            .key(key)
+            .presigned(config)
+            .await
+            .map_err(|e| Error::InvalidContext(
+                format!("failed to presign the GET request: {}", e),
+            ))?;
+        Ok(req.uri().to_string())
+    }
+
+    /// Mints a presigned URL to upload to a caller-supplied logical path.
+    ///
+    /// Normal object keys are content-addressed and unknown before hashing,
+    /// so the caller must name the destination `base_path/path` explicitly.
+    /// The URL is valid for `expires_in` and lets a client `PUT` the object
+    /// without AWS credentials.
+    pub async fn presign_put(
+        &self,
+        path: impl AsRef<str>,
+        expires_in: core::time::Duration,
+    ) -> Result<String, Error> {
+        let key = format!("{}/{}", self.base_path, path.as_ref());
+        let config = PresigningConfig::expires_in(expires_in)
+            .map_err(|e| Error::InvalidContext(
+                format!("invalid presigning config: {}", e),
+            ))?;
+        let req = self.s3.put_object()
+            .bucket(&self.bucket_name)
+            .key(key)
+            .presigned(config)
+            .await
+            .map_err(|e| Error::InvalidContext(
+                format!("failed to presign the PUT request: {}", e),
+            ))?;
+        Ok(req.uri().to_string())
+    }
 }
 
 #[async_trait]
 impl FileSystem for S3FileSystem {
+    type HashedFileOut = S3HashedFileOut;
     type HashedFileIn = S3HashedFileIn;
 
+    fn create_hashed_file(&self) -> Result<Self::HashedFileOut, Error> {
+        S3HashedFileOut::create(
+            self.s3.clone(),
+            self.bucket_name.clone(),
+            self.base_path.clone(),
+            self.codec,
+        )
+    }
+
+    fn create_hashed_file_in(
+        &self,
+        path: impl AsRef<str>,
+    ) -> Result<Self::HashedFileOut, Error> {
+        S3HashedFileOut::create(
+            self.s3.clone(),
+            self.bucket_name.clone(),
+            format!("{}/{}", self.base_path, path.as_ref()),
+            self.codec,
+        )
+    }
+
     async fn open_hashed_file(
         &self,
         path: impl Into<String> + Send,
     ) -> Result<Self::HashedFileIn, Error> {
-        Ok(S3HashedFileIn::open(
+        self.open_checked(path.into(), None).await
+    }
+}
+
+impl S3FileSystem {
+    /// Opens an object after validating its size against `expected_size`.
+    ///
+    /// A truncated or corrupt upload is caught by the cheap `HeadObject`
+    /// probe before the full download and hashing work begins.
+    ///
+    /// `expected_size` is compared against the *stored* object size, i.e. the
+    /// compressed size when this file system has a codec configured
+    /// (`with_codec`). Since callers naturally track the plaintext size they
+    /// wrote, not the compressed size the codec happened to produce, this
+    /// fails with `Error::InvalidContext` whenever a codec is configured;
+    /// size validation is only supported for uncompressed objects.
+    pub async fn open_hashed_file_with_size(
+        &self,
+        path: impl Into<String> + Send,
+        expected_size: u64,
+    ) -> Result<S3HashedFileIn, Error> {
+        self.open_checked(path.into(), Some(expected_size)).await
+    }
+
+    /// Probes the object, short-circuiting when its size is unexpected, then
+    /// downloads it.
+    ///
+    /// The `HeadObject` probe is issued only when a size check is requested;
+    /// the common read path (no `expected_size`) stays a single `GetObject`,
+    /// whose own 404 surfaces a missing object.
+    async fn open_checked(
+        &self,
+        path: String,
+        expected_size: Option<u64>,
+    ) -> Result<S3HashedFileIn, Error> {
+        let key = format!("{}/{}", self.base_path, path);
+        if let Some(expected) = expected_size {
+            if self.codec != Codec::None {
+                return Err(Error::InvalidContext(format!(
+                    "size validation is not supported for {}: this file \
+                     system has a compression codec configured, so the \
+                     stored size does not match the plaintext size a caller \
+                     would expect",
+                    path,
+                )));
+            }
+            let meta = self.head_key(key.clone()).await?
+                .ok_or_else(|| Error::InvalidContext(
+                    format!("no such object on S3: {}", path),
+                ))?;
+            if meta.content_length != expected {
+                return Err(Error::InvalidContext(format!(
+                    "unexpected size for {}: expected {} but got {}",
+                    path,
+                    expected,
+                    meta.content_length,
+                )));
+            }
+        }
+        Ok(S3HashedFileIn::open(self.s3.clone(), self.bucket_name.clone(), key))
+    }
+
+    /// Downloads only a byte range of the object at a given logical path.
+    ///
+    /// Sets the S3 `Range` header (`bytes=start-end`) so that only the
+    /// requested window of `base_path/path` is transferred. Since a partial
+    /// read cannot reproduce the whole-object SHA-256, the returned reader's
+    /// [`verify`](HashedFileIn::verify) is a no-op: verification of
+    /// whole-object integrity requires a full read via `open_hashed_file`.
+    ///
+    /// Only objects stored without compression can be range-read: a window of
+    /// a compressed body is not independently decodable, so the first read of
+    /// a range over an object with a `Content-Encoding` fails.
+    pub fn open_hashed_file_range(
+        &self,
+        path: impl Into<String>,
+        range: core::ops::Range<u64>,
+    ) -> Result<S3HashedFileRangeIn, Error> {
+        Ok(S3HashedFileRangeIn::open(
             self.s3.clone(),
             self.bucket_name.clone(),
             format!("{}/{}", self.base_path, path.into()),
+            range,
         ))
     }
 }
 
+/// Writable file (object) in an S3 bucket.
+///
+/// Plaintext is spooled to a temporary file as it is written, rather than
+/// buffered in memory, so a large partition does not blow up RAM. The digest
+/// and checksum are computed once in [`persist`](HashedFileOut::persist),
+/// over the stored (possibly compressed) bytes, so `verify()` checks storage
+/// integrity. The object key is the base path plus the URL-safe Base64
+/// encoded SHA-256 hash.
+pub struct S3HashedFileOut {
+    s3: Client,
+    bucket_name: String,
+    base_path: String,
+    tempfile: NamedTempFile,
+    file: tokio::fs::File,
+    codec: Codec,
+}
+
+impl S3HashedFileOut {
+    fn create(
+        s3: Client,
+        bucket_name: String,
+        base_path: String,
+        codec: Codec,
+    ) -> Result<Self, Error> {
+        let tempfile = NamedTempFile::new()
+            .map_err(|e| Error::InvalidContext(
+                format!("failed to create a temporary file: {}", e),
+            ))?;
+        let file = tempfile.reopen()
+            .map_err(|e| Error::InvalidContext(
+                format!("failed to reopen the temporary file: {}", e),
+            ))?;
+        Ok(S3HashedFileOut {
+            s3,
+            bucket_name,
+            base_path,
+            tempfile,
+            file: tokio::fs::File::from_std(file),
+            codec,
+        })
+    }
+
+    /// Uploads the stored file in a single `put_object` request.
+    async fn persist_single(
+        &self,
+        path: &std::path::Path,
+        key: String,
+        checksum: String,
+    ) -> Result<(), Error> {
+        let body = ByteStream::from_path(path).await
+            .map_err(|e| Error::InvalidContext(
+                format!("failed to read the temporary file: {}", e),
+            ))?;
+        self.s3.put_object()
+            .bucket(&self.bucket_name)
+            .key(key)
+            .checksum_sha256(&checksum)
+            .metadata(SHA256_METADATA_KEY, &checksum)
+            .set_content_encoding(
+                self.codec.content_encoding().map(|e| e.to_string()),
+            )
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| Error::InvalidContext(
+                format!("failed to upload the content to S3: {}", e),
+            ))?;
+        Ok(())
+    }
+
+    /// Uploads the stored file as a multipart upload, preserving the
+    /// whole-object SHA-256 as user metadata.
+    async fn persist_multipart(
+        &self,
+        path: &std::path::Path,
+        key: String,
+        checksum: String,
+    ) -> Result<(), Error> {
+        let create = self.s3.create_multipart_upload()
+            .bucket(&self.bucket_name)
+            .key(&key)
+            .metadata(SHA256_METADATA_KEY, &checksum)
+            .set_content_encoding(
+                self.codec.content_encoding().map(|e| e.to_string()),
+            )
+            .send()
+            .await
+            .map_err(|e| Error::InvalidContext(
+                format!("failed to create a multipart upload: {}", e),
+            ))?;
+        let upload_id = create.upload_id
+            .ok_or(Error::InvalidContext(
+                format!("no upload id for the multipart upload"),
+            ))?;
+        match self.upload_parts(path, &key, &upload_id).await {
+            Ok(parts) => {
+                let completed = CompletedMultipartUpload::builder()
+                    .set_parts(Some(parts))
+                    .build();
+                self.s3.complete_multipart_upload()
+                    .bucket(&self.bucket_name)
+                    .key(&key)
+                    .upload_id(&upload_id)
+                    .multipart_upload(completed)
+                    .send()
+                    .await
+                    .map_err(|e| Error::InvalidContext(
+                        format!("failed to complete the multipart upload: {}", e),
+                    ))?;
+                Ok(())
+            },
+            Err(err) => {
+                let _ = self.s3.abort_multipart_upload()
+                    .bucket(&self.bucket_name)
+                    .key(&key)
+                    .upload_id(&upload_id)
+                    .send()
+                    .await;
+                Err(err)
+            },
+        }
+    }
+
+    /// Uploads every ~8 MiB part of the stored file and collects the
+    /// completed parts with their ETags.
+    async fn upload_parts(
+        &self,
+        path: &std::path::Path,
+        key: &str,
+        upload_id: &str,
+    ) -> Result<Vec<CompletedPart>, Error> {
+        use tokio::io::AsyncReadExt as _;
+        let mut file = tokio::fs::File::open(path).await
+            .map_err(|e| Error::InvalidContext(
+                format!("failed to open the temporary file: {}", e),
+            ))?;
+        let mut parts = Vec::new();
+        let mut part_number = 1i32;
+        loop {
+            let mut buf = vec![0u8; MULTIPART_PART_SIZE];
+            let mut filled = 0;
+            while filled < buf.len() {
+                let n = file.read(&mut buf[filled..]).await
+                    .map_err(|e| Error::InvalidContext(
+                        format!("failed to read the temporary file: {}", e),
+                    ))?;
+                if n == 0 {
+                    break;
+                }
+                filled += n;
+            }
+            if filled == 0 {
+                break;
+            }
+            buf.truncate(filled);
+            let res = self.s3.upload_part()
+                .bucket(&self.bucket_name)
+                .key(key)
+                .upload_id(upload_id)
+                .part_number(part_number)
+                .body(ByteStream::from(buf))
+                .send()
+                .await
+                .map_err(|e| Error::InvalidContext(
+                    format!("failed to upload a part to S3: {}", e),
+                ))?;
+            parts.push(CompletedPart::builder()
+                .part_number(part_number)
+                .set_e_tag(res.e_tag)
+                .build());
+            part_number += 1;
+        }
+        Ok(parts)
+    }
+}
+
+impl AsyncWrite for S3HashedFileOut {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        // Spools plaintext to the temporary file; compression and hashing of
+        // the stored bytes happen in `persist`.
+        let this = self.get_mut();
+        Pin::new(&mut this.file).poll_write(cx, buf)
+    }
+
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let this = self.get_mut();
+        Pin::new(&mut this.file).poll_flush(cx)
+    }
+
+    fn poll_shutdown(
+        self: Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let this = self.get_mut();
+        Pin::new(&mut this.file).poll_shutdown(cx)
+    }
+}
+
+/// Computes the SHA-256 digest of a file by streaming it in chunks.
+async fn hash_file(path: &std::path::Path) -> Result<ring::digest::Digest, Error> {
+    use tokio::io::AsyncReadExt as _;
+    let mut file = tokio::fs::File::open(path).await
+        .map_err(|e| Error::InvalidContext(
+            format!("failed to open the temporary file: {}", e),
+        ))?;
+    let mut digest = ring::digest::Context::new(&ring::digest::SHA256);
+    let mut buf = vec![0u8; MULTIPART_PART_SIZE];
+    loop {
+        let n = file.read(&mut buf).await
+            .map_err(|e| Error::InvalidContext(
+                format!("failed to read the temporary file: {}", e),
+            ))?;
+        if n == 0 {
+            break;
+        }
+        digest.update(&buf[..n]);
+    }
+    Ok(digest.finish())
+}
+
+#[async_trait]
+impl HashedFileOut for S3HashedFileOut {
+    /// Uploads the stored contents to the S3 bucket.
+    async fn persist(
+        mut self,
+        extension: impl AsRef<str> + Send,
+    ) -> Result<String, Error> {
+        use tokio::io::AsyncWriteExt as _;
+        self.file.flush().await
+            .map_err(|e| Error::InvalidContext(
+                format!("failed to flush the temporary file: {}", e),
+            ))?;
+        // Compresses the spooled plaintext into the stored representation
+        // when a codec is configured; the digest and checksum are computed
+        // over the stored (compressed) bytes so `verify()` checks storage
+        // integrity.
+        let mut encoded: Option<NamedTempFile> = None;
+        let stored_path = match self.codec {
+            Codec::None => self.tempfile.path().to_path_buf(),
+            codec => {
+                // Streams the plaintext through the encoder straight into a
+                // second tempfile, so neither the plaintext nor the
+                // compressed bytes are ever buffered whole in memory.
+                let plain = std::fs::File::open(self.tempfile.path())
+                    .map_err(|e| Error::InvalidContext(
+                        format!("failed to read the temporary file: {}", e),
+                    ))?;
+                let mut tempfile = NamedTempFile::new()
+                    .map_err(|e| Error::InvalidContext(
+                        format!("failed to create a temporary file: {}", e),
+                    ))?;
+                codec.encode_stream(plain, tempfile.as_file_mut())
+                    .map_err(|e| Error::InvalidContext(
+                        format!("failed to compress the content: {}", e),
+                    ))?;
+                let path = tempfile.path().to_path_buf();
+                encoded = Some(tempfile);
+                path
+            },
+        };
+        let digest = hash_file(&stored_path).await?;
+        let id = url_safe_base64_engine.encode(digest.as_ref());
+        let checksum = base64_engine.encode(digest.as_ref());
+        let key = format!("{}/{}.{}", self.base_path, id, extension.as_ref());
+        let length = tokio::fs::metadata(&stored_path).await
+            .map_err(|e| Error::InvalidContext(
+                format!("failed to stat the temporary file: {}", e),
+            ))?
+            .len() as usize;
+        if length > MULTIPART_THRESHOLD {
+            self.persist_multipart(&stored_path, key, checksum).await?;
+        } else {
+            self.persist_single(&stored_path, key, checksum).await?;
+        }
+        drop(encoded);
+        Ok(id)
+    }
+}
+
 type S3GetObjectResult =
     Result<GetObjectOutput, SdkError<GetObjectError, HttpResponse>>;
 
@@ -71,6 +690,13 @@ pin_project! {
         get_object: Pin<Box<dyn Future<Output = S3GetObjectResult> + Send>>,
         checksum: Option<String>,
         body: Option<StreamReader<ByteStream, bytes::Bytes>>,
+        codec: Codec,
+        // Accumulated stored (compressed) bytes, kept until EOF so the body
+        // can be decompressed in one pass for compressed objects.
+        stored: Vec<u8>,
+        // Decompressed plaintext and the current read cursor, populated once
+        // the whole compressed body has been received.
+        decoded: Option<(bytes::Bytes, usize)>,
     }
 }
 
@@ -90,6 +716,97 @@ impl S3HashedFileIn {
             get_object: Box::pin(get_object),
             checksum: None,
             body: None,
+            codec: Codec::None,
+            stored: Vec::new(),
+            decoded: None,
+        }
+    }
+}
+
+pin_project! {
+    /// Readable byte range of an object in an S3 bucket.
+    ///
+    /// Only the requested window is downloaded. As a partial read cannot
+    /// reproduce the whole-object SHA-256, [`verify`](HashedFileIn::verify)
+    /// is a no-op.
+    #[must_use = "streams do nothing unless you poll them"]
+    pub struct S3HashedFileRangeIn {
+        get_object: Option<Pin<Box<dyn Future<Output = S3GetObjectResult> + Send>>>,
+        body: Option<StreamReader<ByteStream, bytes::Bytes>>,
+    }
+}
+
+impl S3HashedFileRangeIn {
+    fn open(
+        s3: Client,
+        bucket_name: String,
+        key: String,
+        range: core::ops::Range<u64>,
+    ) -> Self {
+        // S3 ranges are inclusive on both ends, whereas `Range` excludes the
+        // end; an empty range therefore has no bytes to request and no
+        // `get_object` is ever sent.
+        if range.start >= range.end {
+            return S3HashedFileRangeIn { get_object: None, body: None };
+        }
+        let req = s3.get_object()
+            .bucket(bucket_name)
+            .key(key)
+            .range(format!("bytes={}-{}", range.start, range.end - 1))
+            .send();
+        S3HashedFileRangeIn {
+            get_object: Some(Box::pin(req)),
+            body: None,
+        }
+    }
+}
+
+#[async_trait]
+impl HashedFileIn for S3HashedFileRangeIn {
+    /// No-op: a partial read cannot reproduce the whole-object SHA-256.
+    async fn verify(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl AsyncRead for S3HashedFileRangeIn {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let this = self.project();
+        loop {
+            if let Some(body) = this.body.as_mut() {
+                // 2. reads the contents
+                return Pin::new(body).poll_read(cx, buf);
+            }
+            match this.get_object.as_mut() {
+                // Empty range: nothing was ever requested from S3.
+                None => return Poll::Ready(Ok(())),
+                // 1. waits for a response from S3
+                Some(get_object) => match get_object.as_mut().poll(cx) {
+                    Poll::Ready(Ok(res)) => {
+                        // A window of a compressed body cannot be decoded on
+                        // its own, so the raw slice would be unusable.
+                        if let Some(encoding) = res.content_encoding() {
+                            return Poll::Ready(Err(std::io::Error::new(
+                                std::io::ErrorKind::Other,
+                                Error::InvalidContext(format!(
+                                    "range reads are not supported for \
+                                     compressed objects (Content-Encoding: {})",
+                                    encoding,
+                                )),
+                            )));
+                        }
+                        *this.body = Some(StreamReader::new(res.body));
+                    },
+                    Poll::Pending => return Poll::Pending,
+                    Poll::Ready(Err(err)) => return Poll::Ready(Err(
+                        std::io::Error::new(std::io::ErrorKind::Other, err),
+                    )),
+                },
+            }
         }
     }
 }
@@ -119,37 +836,104 @@ impl AsyncRead for S3HashedFileIn {
     ) -> Poll<std::io::Result<()>> {
         let mut this = self.project();
         loop {
+            // 3. serves already decompressed plaintext (compressed objects)
+            if let Some((decoded, pos)) = this.decoded.as_mut() {
+                let remaining = &decoded[*pos..];
+                let n = remaining.len().min(buf.remaining());
+                buf.put_slice(&remaining[..n]);
+                *pos += n;
+                return Poll::Ready(Ok(()));
+            }
             if let Some(body) = this.body.as_mut() {
-                // 2. reads the contents
-                let last_pos = buf.filled().len();
-                return match Pin::new(body).poll_read(cx, buf) {
+                if *this.codec == Codec::None {
+                    // 2a. streams the contents verbatim, hashing as it goes
+                    let last_pos = buf.filled().len();
+                    return match Pin::new(body).poll_read(cx, buf) {
+                        Poll::Ready(Ok(_)) => {
+                            if buf.filled().len() > last_pos {
+                                let buf = &buf.filled()[last_pos..];
+                                this.digest.update(buf);
+                            }
+                            Poll::Ready(Ok(()))
+                        },
+                        Poll::Pending => Poll::Pending,
+                        Poll::Ready(Err(err)) => Poll::Ready(Err(err)),
+                    };
+                }
+                // 2b. accumulates the whole compressed body, hashing the raw
+                // (stored) bytes, then decompresses it in one pass
+                let mut scratch = [0u8; 8 * 1024];
+                let mut read_buf = ReadBuf::new(&mut scratch);
+                match Pin::new(body).poll_read(cx, &mut read_buf) {
                     Poll::Ready(Ok(_)) => {
-                        if buf.filled().len() > last_pos {
-                            let buf = &buf.filled()[last_pos..];
-                            this.digest.update(buf);
+                        let filled = read_buf.filled();
+                        if filled.is_empty() {
+                            // EOF: decompress the accumulated stored bytes
+                            let decoded = this.codec.decode(this.stored)
+                                .map_err(|e| std::io::Error::new(
+                                    std::io::ErrorKind::Other,
+                                    Error::InvalidContext(format!(
+                                        "failed to decompress the content: {}",
+                                        e,
+                                    )),
+                                ))?;
+                            *this.decoded = Some((
+                                bytes::Bytes::from(decoded),
+                                0,
+                            ));
+                        } else {
+                            this.digest.update(filled);
+                            this.stored.extend_from_slice(filled);
                         }
-                        Poll::Ready(Ok(()))
                     },
-                    Poll::Pending => Poll::Pending,
-                    Poll::Ready(Err(err)) => Poll::Ready(Err(err)),
-                };
+                    Poll::Pending => return Poll::Pending,
+                    Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+                }
             } else {
                 // 1. waits for a response from S3
                 match this.get_object.as_mut().poll(cx) {
                     Poll::Ready(Ok(res)) => {
-                        if res.checksum_sha256.is_some() {
-                            *this.checksum = res.checksum_sha256;
-                            *this.body = Some(StreamReader::new(res.body));
-                        } else {
-                            return Poll::Ready(Err(
-                                std::io::Error::new(
-                                    std::io::ErrorKind::Other,
-                                    Error::InvalidContext(format!(
-                                        "no checksum for the S3 object",
-                                    )),
-                                ),
-                            ));
-                        }
+                        // Prefers the whole-object digest stored as user
+                        // metadata: for multipart objects `checksum_sha256`
+                        // is a composite value (`"base64-N"`) that cannot be
+                        // compared against a plain SHA-256.
+                        let metadata_checksum = res.metadata()
+                            .and_then(|m| m.get(SHA256_METADATA_KEY))
+                            .cloned();
+                        let checksum = match metadata_checksum {
+                            Some(checksum) => checksum,
+                            None => match res.checksum_sha256.clone() {
+                                Some(checksum) if checksum.contains('-') => {
+                                    return Poll::Ready(Err(
+                                        std::io::Error::new(
+                                            std::io::ErrorKind::Other,
+                                            Error::InvalidContext(format!(
+                                                "composite checksum {} \
+                                                 without a whole-object \
+                                                 SHA-256 in metadata",
+                                                checksum,
+                                            )),
+                                        ),
+                                    ));
+                                },
+                                Some(checksum) => checksum,
+                                None => {
+                                    return Poll::Ready(Err(
+                                        std::io::Error::new(
+                                            std::io::ErrorKind::Other,
+                                            Error::InvalidContext(format!(
+                                                "no checksum for the S3 object",
+                                            )),
+                                        ),
+                                    ));
+                                },
+                            },
+                        };
+                        *this.codec = Codec::from_content_encoding(
+                            res.content_encoding(),
+                        );
+                        *this.checksum = Some(checksum);
+                        *this.body = Some(StreamReader::new(res.body));
                     },
                     Poll::Pending => return Poll::Pending,
                     Poll::Ready(Err(err)) => return Poll::Ready(Err(
@@ -0,0 +1,245 @@
+//! Local read-through cache over any `flechasdb::io::FileSystem`.
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD as url_safe_base64_engine;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tempfile::NamedTempFile;
+
+use flechasdb::error::Error;
+use flechasdb::io::{FileSystem, HashedFileIn, HashedFileOut};
+
+/// `FileSystem` that caches objects fetched from an inner file system on
+/// local disk.
+///
+/// Objects are keyed by their content-addressed id (the URL-safe Base64
+/// SHA-256), so cache entries are immutable and never need invalidation.
+/// Reads are served from disk on a hit and fall back to the inner file system
+/// on a miss, writing the downloaded bytes to the cache atomically before
+/// handing them to the reader. Writes pass straight through to the inner file
+/// system.
+pub struct CachingFileSystem<F> {
+    inner: F,
+    cache_dir: PathBuf,
+    max_size: u64,
+    verify_content: bool,
+}
+
+impl<F> CachingFileSystem<F> {
+    /// Wraps `inner` with a cache rooted at `cache_dir`.
+    ///
+    /// `max_size` is the soft upper bound, in bytes, on the total size of the
+    /// cache directory; entries are evicted in least-recently-used order (by
+    /// file mtime) once it is exceeded.
+    ///
+    /// Content verification against the cached entry's id is *off* by
+    /// default; see [`with_content_verification`](Self::with_content_verification)
+    /// for why and how to enable it.
+    pub fn new(
+        inner: F,
+        cache_dir: impl Into<PathBuf>,
+        max_size: u64,
+    ) -> Result<Self, Error> {
+        let cache_dir = cache_dir.into();
+        std::fs::create_dir_all(&cache_dir)
+            .map_err(|e| Error::InvalidContext(
+                format!("failed to create the cache directory: {}", e),
+            ))?;
+        Ok(CachingFileSystem { inner, cache_dir, max_size, verify_content: false })
+    }
+
+    /// Enables or disables re-verifying cached bytes against their id.
+    ///
+    /// The content-addressed id is the SHA-256 of the bytes the inner file
+    /// system *stores*. A plain `S3FileSystem` stores exactly what its reader
+    /// returns, so the cached bytes can be re-hashed against the id. But when
+    /// the inner file system compresses (`with_codec`), the id hashes the
+    /// compressed stored bytes while the reader returns decompressed
+    /// plaintext, so the cached bytes will never match the id.
+    ///
+    /// Defaults to disabled, since wrapping a `with_codec`-enabled file system
+    /// is a common composition and the mismatch above would otherwise throw
+    /// `VerificationFailure` on every cache hit (and the first miss's
+    /// returned reader). Only enable this for an inner file system known to
+    /// store bytes verbatim; storage integrity is still checked by the inner
+    /// reader's `verify()` on a cache miss either way.
+    pub fn with_content_verification(mut self, verify: bool) -> Self {
+        self.verify_content = verify;
+        self
+    }
+
+    /// Cache file name for a logical path: the content-addressed object name.
+    fn cache_name(path: &str) -> &str {
+        path.rsplit('/').next().unwrap_or(path)
+    }
+
+    /// Decodes the whole-object SHA-256 embedded in a logical path, if any.
+    ///
+    /// The id is the URL-safe Base64 of a 32-byte digest; anything else yields
+    /// `None`, which makes `verify()` on a cache hit a no-op.
+    fn expected_digest(path: &str) -> Option<Vec<u8>> {
+        let name = Self::cache_name(path);
+        let id = name.split('.').next().unwrap_or(name);
+        match url_safe_base64_engine.decode(id) {
+            Ok(bytes) if bytes.len() == 32 => Some(bytes),
+            _ => None,
+        }
+    }
+
+    /// Writes `bytes` to `path` atomically via a temporary file and rename.
+    fn store(&self, path: &Path, bytes: &[u8]) -> Result<(), Error> {
+        let mut tempfile = NamedTempFile::new_in(&self.cache_dir)
+            .map_err(|e| Error::InvalidContext(
+                format!("failed to create a cache temporary file: {}", e),
+            ))?;
+        tempfile.write_all(bytes)
+            .and_then(|_| tempfile.flush())
+            .map_err(|e| Error::InvalidContext(
+                format!("failed to write the cache entry: {}", e),
+            ))?;
+        tempfile.persist(path)
+            .map_err(|e| Error::InvalidContext(
+                format!("failed to commit the cache entry: {}", e),
+            ))?;
+        Ok(())
+    }
+
+    /// Evicts least-recently-used entries until the cache fits `max_size`.
+    fn evict(&self) -> Result<(), Error> {
+        let entries = std::fs::read_dir(&self.cache_dir)
+            .map_err(|e| Error::InvalidContext(
+                format!("failed to read the cache directory: {}", e),
+            ))?;
+        let mut files: Vec<(PathBuf, u64, SystemTime)> = Vec::new();
+        let mut total = 0u64;
+        for entry in entries {
+            let entry = entry
+                .map_err(|e| Error::InvalidContext(
+                    format!("failed to read a cache entry: {}", e),
+                ))?;
+            let meta = match entry.metadata() {
+                Ok(meta) if meta.is_file() => meta,
+                _ => continue,
+            };
+            let mtime = meta.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            total += meta.len();
+            files.push((entry.path(), meta.len(), mtime));
+        }
+        // Oldest first, so the least-recently-used entries are dropped first.
+        files.sort_by_key(|(_, _, mtime)| *mtime);
+        for (path, size, _) in files {
+            if total <= self.max_size {
+                break;
+            }
+            if std::fs::remove_file(&path).is_ok() {
+                total = total.saturating_sub(size);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<F> FileSystem for CachingFileSystem<F>
+where
+    F: FileSystem,
+    F::HashedFileIn: HashedFileIn,
+{
+    type HashedFileOut = F::HashedFileOut;
+    type HashedFileIn = CachedHashedFileIn;
+
+    fn create_hashed_file(&self) -> Result<Self::HashedFileOut, Error> {
+        self.inner.create_hashed_file()
+    }
+
+    fn create_hashed_file_in(
+        &self,
+        path: impl AsRef<str>,
+    ) -> Result<Self::HashedFileOut, Error> {
+        self.inner.create_hashed_file_in(path)
+    }
+
+    fn open_hashed_file(
+        &self,
+        path: impl AsRef<str>,
+    ) -> Result<Self::HashedFileIn, Error> {
+        let path = path.as_ref();
+        let cache_path = self.cache_dir.join(Self::cache_name(path));
+        let expected = if self.verify_content {
+            Self::expected_digest(path)
+        } else {
+            None
+        };
+        if cache_path.is_file() {
+            // Hit: touch the entry so LRU eviction keeps it warm.
+            if let Ok(file) = std::fs::OpenOptions::new()
+                .write(true)
+                .open(&cache_path)
+            {
+                let _ = file.set_modified(SystemTime::now());
+            }
+            let body = std::fs::read(&cache_path)
+                .map_err(|e| Error::InvalidContext(
+                    format!("failed to read the cache entry: {}", e),
+                ))?;
+            return Ok(CachedHashedFileIn::new(body, expected));
+        }
+        // Miss: download, verify, then cache the bytes atomically.
+        let mut reader = self.inner.open_hashed_file(path)?;
+        let mut body = Vec::new();
+        reader.read_to_end(&mut body)
+            .map_err(|e| Error::InvalidContext(
+                format!("failed to download the content: {}", e),
+            ))?;
+        reader.verify()?;
+        self.store(&cache_path, &body)?;
+        self.evict()?;
+        Ok(CachedHashedFileIn::new(body, expected))
+    }
+}
+
+/// Readable file served from the local cache.
+pub struct CachedHashedFileIn {
+    body: Vec<u8>,
+    read_pos: usize,
+    expected: Option<Vec<u8>>,
+}
+
+impl CachedHashedFileIn {
+    fn new(body: Vec<u8>, expected: Option<Vec<u8>>) -> Self {
+        CachedHashedFileIn { body, read_pos: 0, expected }
+    }
+}
+
+impl Read for CachedHashedFileIn {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let mut stream = &self.body[self.read_pos..];
+        let n = stream.read(buf)?;
+        self.read_pos += n;
+        Ok(n)
+    }
+}
+
+impl HashedFileIn for CachedHashedFileIn {
+    /// Re-verifies the cached bytes against the content-addressed id.
+    ///
+    /// This is a no-op unless content verification is enabled
+    /// ([`with_content_verification`](CachingFileSystem::with_content_verification)),
+    /// and also when the path's name is not a URL-safe Base64 SHA-256.
+    fn verify(self) -> Result<(), Error> {
+        let expected = match self.expected {
+            Some(expected) => expected,
+            None => return Ok(()),
+        };
+        let digest = ring::digest::digest(&ring::digest::SHA256, &self.body);
+        if digest.as_ref() == expected.as_slice() {
+            Ok(())
+        } else {
+            Err(Error::VerificationFailure(format!(
+                "cached content does not match its id: expected {} but got {}",
+                url_safe_base64_engine.encode(&expected),
+                url_safe_base64_engine.encode(digest.as_ref()),
+            )))
+        }
+    }
+}
@@ -2,8 +2,9 @@
 
 use aws_config::SdkConfig;
 use aws_sdk_s3::Client;
+use aws_sdk_s3::presigning::PresigningConfig;
 use aws_sdk_s3::primitives::ByteStream;
-use aws_sdk_s3::types::ChecksumMode;
+use aws_sdk_s3::types::{ChecksumMode, CompletedMultipartUpload, CompletedPart};
 use base64::Engine;
 use base64::engine::general_purpose::{
     STANDARD as base64_engine,
@@ -15,12 +16,128 @@ use tempfile::NamedTempFile;
 use flechasdb::error::Error;
 use flechasdb::io::{FileSystem, HashedFileIn, HashedFileOut};
 
+/// Object body above which a multipart upload is used instead of a single
+/// `put_object`.
+const MULTIPART_THRESHOLD: u64 = 8 * 1024 * 1024;
+
+/// Size of every `UploadPart` but the last one.
+const MULTIPART_PART_SIZE: usize = 8 * 1024 * 1024;
+
+/// Compression codec applied to stored objects.
+///
+/// The `ring` digest and the S3 `checksum_sha256` are computed over the
+/// *compressed* stored bytes, so `verify()` checks storage integrity; callers
+/// always see the decompressed plaintext.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Codec {
+    /// No compression.
+    #[default]
+    None,
+    /// gzip (`Content-Encoding: gzip`).
+    Gzip,
+    /// Zstandard (`Content-Encoding: zstd`).
+    Zstd,
+}
+
+impl Codec {
+    /// `Content-Encoding` value for the codec, if any.
+    fn content_encoding(self) -> Option<&'static str> {
+        match self {
+            Codec::None => None,
+            Codec::Gzip => Some("gzip"),
+            Codec::Zstd => Some("zstd"),
+        }
+    }
+
+    /// Resolves the codec from an object's `Content-Encoding`.
+    fn from_content_encoding(encoding: Option<&str>) -> Self {
+        match encoding {
+            Some("gzip") => Codec::Gzip,
+            Some("zstd") => Codec::Zstd,
+            _ => Codec::None,
+        }
+    }
+
+    /// Streams `src` through the codec into `dst`.
+    ///
+    /// Neither side is held in memory in full, so a multi-gigabyte partition
+    /// is compressed with a bounded footprint.
+    fn encode_stream(
+        self,
+        mut src: impl Read,
+        dst: impl Write,
+    ) -> std::io::Result<()> {
+        match self {
+            Codec::None => {
+                let mut dst = dst;
+                std::io::copy(&mut src, &mut dst)?;
+                Ok(())
+            },
+            Codec::Gzip => {
+                use flate2::Compression;
+                use flate2::write::GzEncoder;
+                let mut encoder = GzEncoder::new(dst, Compression::default());
+                std::io::copy(&mut src, &mut encoder)?;
+                encoder.finish()?;
+                Ok(())
+            },
+            Codec::Zstd => {
+                let mut encoder = zstd::stream::Encoder::new(dst, 0)?;
+                std::io::copy(&mut src, &mut encoder)?;
+                encoder.finish()?;
+                Ok(())
+            },
+        }
+    }
+
+    /// Decompresses the stored representation back to plaintext.
+    fn decode(self, stored: &[u8]) -> std::io::Result<Vec<u8>> {
+        match self {
+            Codec::None => Ok(stored.to_vec()),
+            Codec::Gzip => {
+                use flate2::read::GzDecoder;
+                let mut out = Vec::new();
+                GzDecoder::new(stored).read_to_end(&mut out)?;
+                Ok(out)
+            },
+            Codec::Zstd => zstd::stream::decode_all(stored),
+        }
+    }
+}
+
+/// User metadata key holding the whole-object SHA-256 digest (Base64).
+///
+/// S3 exposes this as `x-amz-meta-sha256`. It is written on every upload so
+/// that [`S3HashedFileIn`] can verify multipart objects, whose
+/// `checksum_sha256` is a *composite* value (`"base64-N"`) and cannot be
+/// compared against a plain whole-object digest.
+const SHA256_METADATA_KEY: &str = "sha256";
+
+/// Lightweight metadata about an object, as returned by `HeadObject`.
+#[derive(Clone, Debug)]
+pub struct ObjectMeta {
+    /// Size of the *stored* object body in bytes.
+    ///
+    /// When the file system that wrote the object had a codec configured
+    /// (`with_codec`), this is the compressed size, not the plaintext size a
+    /// caller would have tracked while writing.
+    pub content_length: u64,
+    /// Whole-object SHA-256 digest (Base64), if available.
+    ///
+    /// Prefers the `x-amz-meta-sha256` user metadata over the object's
+    /// `checksum_sha256`, which is composite for multipart objects.
+    pub sha256: Option<String>,
+    /// Last modification time reported by S3.
+    pub last_modified: Option<aws_smithy_types::DateTime>,
+}
+
 /// `FileSystem` on Amazon S3.
 pub struct S3FileSystem {
     runtime_handle: tokio::runtime::Handle,
     s3: aws_sdk_s3::Client,
     bucket_name: String,
     base_path: String,
+    codec: Codec,
 }
 
 impl S3FileSystem {
@@ -39,8 +156,123 @@ impl S3FileSystem {
             s3,
             bucket_name: bucket_name.into(),
             base_path: base_path.into(),
+            codec: Codec::None,
+        }
+    }
+
+    /// Applies a compression codec to objects written through this file
+    /// system.
+    ///
+    /// Reads transparently decompress based on the object's
+    /// `Content-Encoding`, so this only affects newly written objects.
+    pub fn with_codec(mut self, codec: Codec) -> Self {
+        self.codec = codec;
+        self
+    }
+
+    /// Probes an object's existence and metadata without downloading it.
+    ///
+    /// Issues `HeadObject` with checksums enabled and returns `Ok(None)` when
+    /// the object does not exist, or `Ok(Some(meta))` with its content length,
+    /// SHA-256 checksum, and last-modified time otherwise.
+    ///
+    /// Blocks until the request completes.
+    pub fn head(
+        &self,
+        path: impl AsRef<str>,
+    ) -> Result<Option<ObjectMeta>, Error> {
+        let key = format!("{}/{}", self.base_path, path.as_ref());
+        self.head_key(key)
+    }
+
+    /// Probes an object by its fully resolved key.
+    fn head_key(&self, key: String) -> Result<Option<ObjectMeta>, Error> {
+        let res = self.s3.head_object()
+            .bucket(&self.bucket_name)
+            .key(key)
+            .checksum_mode(ChecksumMode::Enabled)
+            .send();
+        match self.runtime_handle.block_on(res) {
+            Ok(res) => {
+                let sha256 = res.metadata()
+                    .and_then(|m| m.get(SHA256_METADATA_KEY))
+                    .cloned()
+                    .or_else(|| res.checksum_sha256().map(|c| c.to_string()));
+                Ok(Some(ObjectMeta {
+                    content_length: res.content_length().unwrap_or(0) as u64,
+                    sha256,
+                    last_modified: res.last_modified().cloned(),
+                }))
+            },
+            Err(e) => {
+                if e.as_service_error().map(|e| e.is_not_found()) == Some(true) {
+                    Ok(None)
+                } else {
+                    Err(Error::InvalidContext(
+                        format!("failed to head the object on S3: {}", e),
+                    ))
+                }
+            },
         }
     }
+
+    /// Mints a presigned URL to download the object at a given logical path.
+    ///
+    /// The URL is valid for `expires_in` and lets a client `GET` the object
+    /// `base_path/path` without AWS credentials.
+    ///
+    /// Blocks until the URL is signed.
+    pub fn presign_get(
+        &self,
+        path: impl AsRef<str>,
+        expires_in: std::time::Duration,
+    ) -> Result<String, Error> {
+        let key = format!("{}/{}", self.base_path, path.as_ref());
+        let config = PresigningConfig::expires_in(expires_in)
+            .map_err(|e| Error::InvalidContext(
+                format!("invalid presigning config: {}", e),
+            ))?;
+        let req = self.s3.get_object()
+            .bucket(&self.bucket_name)
+            .key(key)
+            .presigned(config);
+        let req = self.runtime_handle
+            .block_on(req)
+            .map_err(|e| Error::InvalidContext(
+                format!("failed to presign the GET request: {}", e),
+            ))?;
+        Ok(req.uri().to_string())
+    }
+
+    /// Mints a presigned URL to upload to a caller-supplied logical path.
+    ///
+    /// Normal object keys are content-addressed and unknown before hashing,
+    /// so the caller must name the destination `base_path/path` explicitly.
+    /// The URL is valid for `expires_in` and lets a client `PUT` the object
+    /// without AWS credentials.
+    ///
+    /// Blocks until the URL is signed.
+    pub fn presign_put(
+        &self,
+        path: impl AsRef<str>,
+        expires_in: std::time::Duration,
+    ) -> Result<String, Error> {
+        let key = format!("{}/{}", self.base_path, path.as_ref());
+        let config = PresigningConfig::expires_in(expires_in)
+            .map_err(|e| Error::InvalidContext(
+                format!("invalid presigning config: {}", e),
+            ))?;
+        let req = self.s3.put_object()
+            .bucket(&self.bucket_name)
+            .key(key)
+            .presigned(config);
+        let req = self.runtime_handle
+            .block_on(req)
+            .map_err(|e| Error::InvalidContext(
+                format!("failed to presign the PUT request: {}", e),
+            ))?;
+        Ok(req.uri().to_string())
+    }
 }
 
 impl FileSystem for S3FileSystem {
@@ -53,6 +285,7 @@ impl FileSystem for S3FileSystem {
             self.s3.clone(),
             self.bucket_name.clone(),
             self.base_path.clone(),
+            self.codec,
         )
     }
 
@@ -65,6 +298,7 @@ impl FileSystem for S3FileSystem {
             self.s3.clone(),
             self.bucket_name.clone(),
             format!("{}/{}", self.base_path, path.as_ref()),
+            self.codec,
         )
     }
 
@@ -72,11 +306,97 @@ impl FileSystem for S3FileSystem {
         &self,
         path: impl AsRef<str>,
     ) -> Result<Self::HashedFileIn, Error> {
+        self.open_checked(path.as_ref(), None)
+    }
+}
+
+impl S3FileSystem {
+    /// Opens an object after validating its size against `expected_size`.
+    ///
+    /// A truncated or corrupt upload is caught by the cheap `HeadObject`
+    /// probe before the full download and hashing work begins.
+    ///
+    /// `expected_size` is compared against the *stored* object size, i.e. the
+    /// compressed size when this file system has a codec configured
+    /// (`with_codec`). Since callers naturally track the plaintext size they
+    /// wrote, not the compressed size the codec happened to produce, this
+    /// fails with `Error::InvalidContext` whenever a codec is configured;
+    /// size validation is only supported for uncompressed objects.
+    pub fn open_hashed_file_with_size(
+        &self,
+        path: impl AsRef<str>,
+        expected_size: u64,
+    ) -> Result<S3HashedFileIn, Error> {
+        self.open_checked(path.as_ref(), Some(expected_size))
+    }
+
+    /// Probes the object, short-circuiting when its size is unexpected, then
+    /// downloads it.
+    ///
+    /// The `HeadObject` probe is issued only when a size check is requested;
+    /// the common read path (no `expected_size`) stays a single `GetObject`,
+    /// whose own 404 surfaces a missing object.
+    fn open_checked(
+        &self,
+        path: &str,
+        expected_size: Option<u64>,
+    ) -> Result<S3HashedFileIn, Error> {
+        let key = format!("{}/{}", self.base_path, path);
+        if let Some(expected) = expected_size {
+            if self.codec != Codec::None {
+                return Err(Error::InvalidContext(format!(
+                    "size validation is not supported for {}: this file \
+                     system has a compression codec configured, so the \
+                     stored size does not match the plaintext size a caller \
+                     would expect",
+                    path,
+                )));
+            }
+            let meta = self.head_key(key.clone())?
+                .ok_or_else(|| Error::InvalidContext(
+                    format!("no such object on S3: {}", path),
+                ))?;
+            if meta.content_length != expected {
+                return Err(Error::InvalidContext(format!(
+                    "unexpected size for {}: expected {} but got {}",
+                    path,
+                    expected,
+                    meta.content_length,
+                )));
+            }
+        }
         S3HashedFileIn::open(
+            self.runtime_handle.clone(),
+            &self.s3,
+            self.bucket_name.clone(),
+            key,
+        )
+    }
+
+    /// Downloads only a byte range of the object at a given logical path.
+    ///
+    /// Sets the S3 `Range` header (`bytes=start-end`) so that only the
+    /// requested window of `base_path/path` is transferred. Since a partial
+    /// read cannot reproduce the whole-object SHA-256, the returned reader's
+    /// [`verify`](HashedFileIn::verify) is a no-op: verification of
+    /// whole-object integrity requires a full read via `open_hashed_file`.
+    ///
+    /// Only objects stored without compression can be range-read: a window of
+    /// a compressed body is not independently decodable, so a range read of an
+    /// object with a `Content-Encoding` fails with `Error::InvalidContext`.
+    ///
+    /// Blocks until the download completes.
+    pub fn open_hashed_file_range(
+        &self,
+        path: impl AsRef<str>,
+        range: std::ops::Range<u64>,
+    ) -> Result<S3HashedFileRangeIn, Error> {
+        S3HashedFileRangeIn::open(
             self.runtime_handle.clone(),
             &self.s3,
             self.bucket_name.clone(),
             format!("{}/{}", self.base_path, path.as_ref()),
+            range,
         )
     }
 }
@@ -93,7 +413,7 @@ pub struct S3HashedFileOut {
     tempfile: NamedTempFile,
     bucket_name: String,
     base_path: String,
-    digest: ring::digest::Context,
+    codec: Codec,
 }
 
 impl S3HashedFileOut {
@@ -102,6 +422,7 @@ impl S3HashedFileOut {
         s3: Client,
         bucket_name: String,
         base_path: String,
+        codec: Codec,
     ) -> Result<Self, Error> {
         let tempfile = NamedTempFile::new()?;
         Ok(S3HashedFileOut {
@@ -110,14 +431,15 @@ impl S3HashedFileOut {
             tempfile,
             bucket_name,
             base_path,
-            digest: ring::digest::Context::new(&ring::digest::SHA256),
+            codec,
         })
     }
 }
 
 impl Write for S3HashedFileOut {
     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
-        self.digest.update(buf);
+        // Spools plaintext; compression and hashing of the stored bytes
+        // happen in `persist`.
         self.tempfile.write(buf)
     }
 
@@ -134,19 +456,71 @@ impl HashedFileOut for S3HashedFileOut {
     /// otherwise fails with `Error::InvalidContext`.
     fn persist(mut self, extension: impl AsRef<str>) -> Result<String, Error> {
         self.flush()?;
-        let digest = self.digest.finish();
+        // Compresses the spooled plaintext into the stored representation when
+        // a codec is configured; the digest and checksum are computed over the
+        // stored (compressed) bytes so `verify()` checks storage integrity.
+        let mut encoded: Option<NamedTempFile> = None;
+        let stored_path = match self.codec {
+            Codec::None => self.tempfile.path().to_path_buf(),
+            codec => {
+                // Streams the plaintext through the encoder straight into a
+                // second tempfile, so neither the plaintext nor the compressed
+                // bytes are ever buffered whole in memory.
+                let plain = std::fs::File::open(self.tempfile.path())
+                    .map_err(|e| Error::InvalidContext(
+                        format!("failed to read the temporary file: {}", e),
+                    ))?;
+                let mut tempfile = NamedTempFile::new()?;
+                codec.encode_stream(plain, tempfile.as_file_mut())
+                    .map_err(|e| Error::InvalidContext(
+                        format!("failed to compress the content: {}", e),
+                    ))?;
+                tempfile.flush()?;
+                let path = tempfile.path().to_path_buf();
+                encoded = Some(tempfile);
+                path
+            },
+        };
+        let digest = hash_file(&stored_path)?;
         let id = url_safe_base64_engine.encode(digest.as_ref());
         let checksum = base64_engine.encode(digest.as_ref());
         let key = format!("{}/{}.{}", self.base_path, id, extension.as_ref());
+        let length = std::fs::metadata(&stored_path)
+            .map_err(|e| Error::InvalidContext(
+                format!("failed to stat the temporary file: {}", e),
+            ))?
+            .len();
+        let content_encoding = self.codec.content_encoding();
+        if length > MULTIPART_THRESHOLD {
+            self.persist_multipart(&stored_path, &key, checksum, content_encoding)?;
+        } else {
+            self.persist_single(&stored_path, &key, checksum, content_encoding)?;
+        }
+        drop(encoded);
+        Ok(id)
+    }
+}
+
+impl S3HashedFileOut {
+    /// Uploads the stored file in a single `put_object` request.
+    fn persist_single(
+        &self,
+        path: &std::path::Path,
+        key: &str,
+        checksum: String,
+        content_encoding: Option<&str>,
+    ) -> Result<(), Error> {
         let body = self.runtime_handle
-            .block_on(ByteStream::from_path(self.tempfile.path()))
+            .block_on(ByteStream::from_path(path))
             .map_err(|e| Error::InvalidContext(
                 format!("failed to read the temporary file: {}", e),
             ))?;
         let res = self.s3.put_object()
-            .bucket(self.bucket_name)
+            .bucket(&self.bucket_name)
             .key(key)
-            .checksum_sha256(checksum)
+            .checksum_sha256(&checksum)
+            .metadata(SHA256_METADATA_KEY, &checksum)
+            .set_content_encoding(content_encoding.map(|e| e.to_string()))
             .body(body)
             .send();
         self.runtime_handle
@@ -154,8 +528,140 @@ impl HashedFileOut for S3HashedFileOut {
             .map_err(|e| Error::InvalidContext(
                 format!("failed to upload the content to S3: {}", e),
             ))?;
-        Ok(id)
+        Ok(())
+    }
+
+    /// Uploads the stored file as a multipart upload.
+    ///
+    /// S3 reports a composite `checksum_sha256` for the resulting object, so
+    /// the whole-object digest is preserved as user metadata under
+    /// [`SHA256_METADATA_KEY`].
+    fn persist_multipart(
+        &self,
+        path: &std::path::Path,
+        key: &str,
+        checksum: String,
+        content_encoding: Option<&str>,
+    ) -> Result<(), Error> {
+        let create = self.s3.create_multipart_upload()
+            .bucket(&self.bucket_name)
+            .key(key)
+            .metadata(SHA256_METADATA_KEY, &checksum)
+            .set_content_encoding(content_encoding.map(|e| e.to_string()))
+            .send();
+        let create = self.runtime_handle
+            .block_on(create)
+            .map_err(|e| Error::InvalidContext(
+                format!("failed to create a multipart upload: {}", e),
+            ))?;
+        let upload_id = create.upload_id
+            .ok_or(Error::InvalidContext(
+                format!("no upload id for the multipart upload"),
+            ))?;
+        // Streams the stored file in ~8 MiB parts, aborting the upload on any
+        // failure so that no dangling parts are left billed in the bucket.
+        match self.upload_parts(path, key, &upload_id) {
+            Ok(parts) => {
+                let completed = CompletedMultipartUpload::builder()
+                    .set_parts(Some(parts))
+                    .build();
+                let complete = self.s3.complete_multipart_upload()
+                    .bucket(&self.bucket_name)
+                    .key(key)
+                    .upload_id(&upload_id)
+                    .multipart_upload(completed)
+                    .send();
+                self.runtime_handle
+                    .block_on(complete)
+                    .map_err(|e| Error::InvalidContext(
+                        format!("failed to complete the multipart upload: {}", e),
+                    ))?;
+                Ok(())
+            },
+            Err(err) => {
+                let abort = self.s3.abort_multipart_upload()
+                    .bucket(&self.bucket_name)
+                    .key(key)
+                    .upload_id(&upload_id)
+                    .send();
+                let _ = self.runtime_handle.block_on(abort);
+                Err(err)
+            },
+        }
     }
+
+    /// Uploads every part of the stored file and collects the completed
+    /// parts with their ETags.
+    fn upload_parts(
+        &self,
+        path: &std::path::Path,
+        key: &str,
+        upload_id: &str,
+    ) -> Result<Vec<CompletedPart>, Error> {
+        let mut file = std::fs::File::open(path)
+            .map_err(|e| Error::InvalidContext(
+                format!("failed to open the temporary file: {}", e),
+            ))?;
+        let mut parts = Vec::new();
+        let mut part_number = 1i32;
+        loop {
+            let mut buf = vec![0u8; MULTIPART_PART_SIZE];
+            let mut filled = 0;
+            while filled < buf.len() {
+                let n = file.read(&mut buf[filled..])
+                    .map_err(|e| Error::InvalidContext(
+                        format!("failed to read the temporary file: {}", e),
+                    ))?;
+                if n == 0 {
+                    break;
+                }
+                filled += n;
+            }
+            if filled == 0 {
+                break;
+            }
+            buf.truncate(filled);
+            let upload = self.s3.upload_part()
+                .bucket(&self.bucket_name)
+                .key(key)
+                .upload_id(upload_id)
+                .part_number(part_number)
+                .body(ByteStream::from(buf))
+                .send();
+            let res = self.runtime_handle
+                .block_on(upload)
+                .map_err(|e| Error::InvalidContext(
+                    format!("failed to upload a part to S3: {}", e),
+                ))?;
+            parts.push(CompletedPart::builder()
+                .part_number(part_number)
+                .set_e_tag(res.e_tag)
+                .build());
+            part_number += 1;
+        }
+        Ok(parts)
+    }
+}
+
+/// Computes the SHA-256 digest of a file by streaming it in chunks.
+fn hash_file(path: &std::path::Path) -> Result<ring::digest::Digest, Error> {
+    let mut file = std::fs::File::open(path)
+        .map_err(|e| Error::InvalidContext(
+            format!("failed to open the temporary file: {}", e),
+        ))?;
+    let mut digest = ring::digest::Context::new(&ring::digest::SHA256);
+    let mut buf = vec![0u8; MULTIPART_PART_SIZE];
+    loop {
+        let n = file.read(&mut buf)
+            .map_err(|e| Error::InvalidContext(
+                format!("failed to read the temporary file: {}", e),
+            ))?;
+        if n == 0 {
+            break;
+        }
+        digest.update(&buf[..n]);
+    }
+    Ok(digest.finish())
 }
 
 /// Readable file (object) in an S3 bucket.
@@ -188,21 +694,54 @@ impl S3HashedFileIn {
             .map_err(|e| Error::InvalidContext(
                 format!("failed to request the content from S3: {}", e),
             ))?;
-        let checksum = res.checksum_sha256
-            .ok_or(Error::InvalidContext(
-                format!("no checksum for the content from S3"),
-            ))?;
-        let body = runtime_handle
+        // Prefers the whole-object digest stored as user metadata: for
+        // multipart objects `checksum_sha256` is a composite value
+        // (`"base64-N"`) that cannot be compared against a plain SHA-256.
+        let metadata_checksum = res.metadata()
+            .and_then(|m| m.get(SHA256_METADATA_KEY))
+            .cloned();
+        let checksum = match metadata_checksum {
+            Some(checksum) => checksum,
+            None => {
+                let checksum = res.checksum_sha256
+                    .ok_or(Error::InvalidContext(
+                        format!("no checksum for the content from S3"),
+                    ))?;
+                if checksum.contains('-') {
+                    return Err(Error::InvalidContext(format!(
+                        "composite checksum {} without a whole-object SHA-256 \
+                         in metadata",
+                        checksum,
+                    )));
+                }
+                checksum
+            },
+        };
+        let codec = Codec::from_content_encoding(res.content_encoding());
+        let stored = runtime_handle
             .block_on(res.body.collect())
             .map_err(|e| Error::InvalidContext(
                 format!("failed to read the content from S3: {}", e),
             ))?
             .into_bytes();
+        // The digest is computed over the stored (compressed) bytes so that
+        // `verify()` checks storage integrity, while callers consume the
+        // decompressed plaintext.
+        let mut digest = ring::digest::Context::new(&ring::digest::SHA256);
+        digest.update(&stored);
+        let body = match codec {
+            Codec::None => stored,
+            codec => codec.decode(&stored)
+                .map(bytes::Bytes::from)
+                .map_err(|e| Error::InvalidContext(
+                    format!("failed to decompress the content: {}", e),
+                ))?,
+        };
         Ok(S3HashedFileIn {
             body,
             read_pos: 0,
             checksum,
-            digest: ring::digest::Context::new(&ring::digest::SHA256),
+            digest,
         })
     }
 }
@@ -212,11 +751,90 @@ impl Read for S3HashedFileIn {
         let mut stream = &self.body[self.read_pos..];
         let n = stream.read(buf)?;
         self.read_pos += n;
-        self.digest.update(&buf[..n]);
         Ok(n)
     }
 }
 
+/// Readable byte range of an object in an S3 bucket.
+///
+/// Only the requested window is downloaded. As a partial read cannot
+/// reproduce the whole-object SHA-256, [`verify`](HashedFileIn::verify) is a
+/// no-op.
+pub struct S3HashedFileRangeIn {
+    body: bytes::Bytes,
+    read_pos: usize,
+}
+
+impl S3HashedFileRangeIn {
+    /// Downloads a byte range from an S3 bucket.
+    ///
+    /// Blocks until the download completes.
+    /// This function must be called within the context of a Tokio runtime,
+    /// otherwise fails with `Error::InvalidContext`.
+    fn open(
+        runtime_handle: tokio::runtime::Handle,
+        s3: &Client,
+        bucket_name: String,
+        key: String,
+        range: std::ops::Range<u64>,
+    ) -> Result<Self, Error> {
+        // S3 ranges are inclusive on both ends, whereas `Range` excludes the
+        // end; an empty range therefore has no bytes to request.
+        if range.start >= range.end {
+            return Ok(S3HashedFileRangeIn {
+                body: bytes::Bytes::new(),
+                read_pos: 0,
+            });
+        }
+        let header = format!("bytes={}-{}", range.start, range.end - 1);
+        let res = s3.get_object()
+            .bucket(bucket_name)
+            .key(key)
+            .range(header)
+            .send();
+        let res = runtime_handle
+            .block_on(res)
+            .map_err(|e| Error::InvalidContext(
+                format!("failed to request the content from S3: {}", e),
+            ))?;
+        // A window of a compressed body cannot be decoded on its own, so the
+        // raw slice handed back would be unusable; reject such range reads.
+        if let Some(encoding) = res.content_encoding() {
+            return Err(Error::InvalidContext(format!(
+                "range reads are not supported for compressed objects \
+                 (Content-Encoding: {})",
+                encoding,
+            )));
+        }
+        let body = runtime_handle
+            .block_on(res.body.collect())
+            .map_err(|e| Error::InvalidContext(
+                format!("failed to read the content from S3: {}", e),
+            ))?
+            .into_bytes();
+        Ok(S3HashedFileRangeIn {
+            body,
+            read_pos: 0,
+        })
+    }
+}
+
+impl Read for S3HashedFileRangeIn {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let mut stream = &self.body[self.read_pos..];
+        let n = stream.read(buf)?;
+        self.read_pos += n;
+        Ok(n)
+    }
+}
+
+impl HashedFileIn for S3HashedFileRangeIn {
+    /// No-op: a partial read cannot reproduce the whole-object SHA-256.
+    fn verify(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
 impl HashedFileIn for S3HashedFileIn {
     fn verify(self) -> Result<(), Error> {
         let digest = self.digest.finish();